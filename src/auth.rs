@@ -1,31 +1,162 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
 use vaultrs::api::AuthInfo;
 use vaultrs::client::{VaultClient, VaultClientSettingsBuilder};
 
 use crate::error::Result;
-use crate::read_token_from;
+use crate::{build_vault_client, read_token_from};
 
 const K8S_JWT: &str = "K8S_JWT";
 const SERVICE_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
 
+/// The supported ways to authenticate against Vault before a `SecretClient` can read or write
+/// secrets.
+///
+/// Selecting the method at runtime (e.g. from a `VAULT_AUTH_METHOD` environment variable) lets
+/// the same binary use `AppRole` in CI and `Kubernetes` in-cluster without code changes.
+#[derive(Debug, Clone)]
+pub enum AuthMethod {
+    /// Authenticate via the Kubernetes auth method using the pod's service account JWT.
+    ///
+    /// `token_path` overrides the file the service account JWT is read from, for projected tokens
+    /// mounted at a non-default path. The `K8S_JWT` env var, when set, is used first either way;
+    /// otherwise the JWT is read from `token_path`, or from
+    /// `/var/run/secrets/kubernetes.io/serviceaccount/token` when it is `None`.
+    Kubernetes {
+        role: String,
+        token_path: Option<String>,
+    },
+    /// Authenticate via the AppRole auth method.
+    AppRole { role_id: String, secret_id: String },
+    /// Authenticate via the JWT auth method using an externally issued JWT.
+    Jwt { role: String, jwt: String },
+    /// Use a pre-existing Vault token directly, skipping the login step.
+    Token { token: String },
+}
+
+/// The outcome of a login, carrying just enough of the `AuthInfo` lease data for the
+/// auto-renewal task to decide when and how to refresh the token.
+pub(crate) struct LoginResult {
+    pub token: String,
+    pub lease_duration: u64,
+    pub renewable: bool,
+}
+
+/// The state [`auto_renew`] needs to keep a `SecretClient`'s token fresh: how to reach Vault
+/// again (`auth_mount`, `method`) and the lease info from the most recent login or renewal.
+pub(crate) struct RenewState {
+    pub auth_mount: String,
+    pub method: AuthMethod,
+    pub lease_duration: u64,
+    pub renewable: bool,
+    pub namespace: Option<String>,
+}
+
 pub(crate) async fn login(
     vault_address: &str,
     auth_mount_path: &str,
-    role: &str,
-) -> Result<AuthInfo> {
-    let jwt = service_account_jwt()?;
-    let client = VaultClient::new(
-        VaultClientSettingsBuilder::default()
-            .address(vault_address)
-            .build()?,
-    )?;
-    Ok(vaultrs::auth::kubernetes::login(&client, auth_mount_path, role, &jwt).await?)
+    method: &AuthMethod,
+    namespace: Option<&str>,
+) -> Result<LoginResult> {
+    if let AuthMethod::Token { token } = method {
+        return Ok(LoginResult {
+            token: token.clone(),
+            lease_duration: 0,
+            renewable: false,
+        });
+    }
+
+    let mut settings = VaultClientSettingsBuilder::default();
+    settings.address(vault_address);
+    if let Some(namespace) = namespace {
+        settings.namespace(namespace.to_string());
+    }
+    let client = VaultClient::new(settings.build()?)?;
+
+    let auth_info: AuthInfo = match method {
+        AuthMethod::Kubernetes { role, token_path } => {
+            let jwt = service_account_jwt(token_path.as_deref())?;
+            vaultrs::auth::kubernetes::login(&client, auth_mount_path, role, &jwt).await?
+        }
+        AuthMethod::AppRole { role_id, secret_id } => {
+            vaultrs::auth::approle::login(&client, auth_mount_path, role_id, secret_id).await?
+        }
+        AuthMethod::Jwt { role, jwt } => {
+            vaultrs::auth::jwt::login(&client, auth_mount_path, role, jwt).await?
+        }
+        AuthMethod::Token { .. } => unreachable!("handled above"),
+    };
+
+    Ok(LoginResult {
+        token: auth_info.client_token,
+        lease_duration: auth_info.lease_duration,
+        renewable: auth_info.renewable,
+    })
+}
+
+/// Keeps a `SecretClient`'s token fresh for as long as it runs, swapping `client` in place.
+///
+/// Renewable tokens are renewed via `vaultrs::token::renew_self` at roughly half their TTL, using
+/// the already-cached `VaultClient` so a successful renewal never needs a new one. Once a token
+/// can no longer be renewed (non-renewable, or renewal rejected because it is past its max TTL),
+/// the task logs in again with the original `AuthMethod` and only then rebuilds the `VaultClient`
+/// with the new token. The task exits once a lease has no duration to wait on or a fresh login
+/// fails.
+pub(crate) async fn auto_renew(
+    address: String,
+    client: Arc<RwLock<VaultClient>>,
+    mut state: RenewState,
+) {
+    loop {
+        if state.lease_duration == 0 {
+            return;
+        }
+
+        tokio::time::sleep(Duration::from_secs(state.lease_duration.max(2) / 2)).await;
+
+        if state.renewable {
+            let current = client.read().await.clone();
+            let renewed = vaultrs::token::renew_self(&current, None).await;
+
+            if let Ok(auth_info) = renewed {
+                // renew_self extends the existing token's lease and returns that same token, so
+                // the cached VaultClient is still valid: just update the lease info.
+                state.lease_duration = auth_info.lease_duration;
+                state.renewable = auth_info.renewable;
+                continue;
+            }
+        }
+
+        match login(
+            &address,
+            &state.auth_mount,
+            &state.method,
+            state.namespace.as_deref(),
+        )
+        .await
+        {
+            Ok(result) => {
+                match build_vault_client(&address, &result.token, state.namespace.as_deref()) {
+                    Ok(new_client) => {
+                        *client.write().await = new_client;
+                        state.lease_duration = result.lease_duration;
+                        state.renewable = result.renewable;
+                    }
+                    Err(_) => return,
+                }
+            }
+            Err(_) => return,
+        }
+    }
 }
 
-fn service_account_jwt() -> Result<String> {
+fn service_account_jwt(token_path: Option<&str>) -> Result<String> {
     let env_token = std::env::var(K8S_JWT);
 
     match env_token {
         Ok(token) => Ok(token),
-        Err(_) => read_token_from(SERVICE_TOKEN_PATH),
+        Err(_) => read_token_from(token_path.unwrap_or(SERVICE_TOKEN_PATH), false),
     }
 }
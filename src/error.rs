@@ -15,6 +15,8 @@ pub enum VaultierError {
     IO(#[from] io::Error),
     #[error("Path not found: {0}")]
     PathNotFound(String),
+    #[error("Token file {0} is group- or world-readable; refusing to read it")]
+    InsecureTokenFilePermissions(String),
     #[cfg(feature = "metadata")]
     #[error("Unexpected response from the Vault API: {status}. Message: {message}.")]
     Api {
@@ -24,7 +26,9 @@ pub enum VaultierError {
     #[cfg(feature = "metadata")]
     #[error("Failed to send request: {0}")]
     Reqwest(#[from] reqwest::Error),
-    #[cfg(feature = "metadata")]
+    // `serde_json` must be a dependency of the `read` feature (not just `metadata`) for this
+    // variant and src/lib.rs's read_fields to build under `--features read`.
+    #[cfg(any(feature = "metadata", feature = "read"))]
     #[error("Json related error: {0}")]
     Json(#[from] serde_json::Error),
     #[cfg(feature = "metadata")]
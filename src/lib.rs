@@ -15,12 +15,12 @@
 //! let base_path = String::from("<base_path>");
 //!
 //! // With token or default feature enabled
-//! let client = SecretClient::new(address, mount, base_path, None).unwrap();
+//! let client = SecretClient::new(address, mount, base_path, None, None).unwrap();
 //!
 //! // With auth feature enabled
 //! let auth_mount = "<mount to vault auth>";
 //! let role = "<your role>";
-//! let client = SecretClient::create(address, auth_mount, role, mount, base_path).unwrap();
+//! let client = SecretClient::create(address, auth_mount, role, mount, base_path, None).unwrap();
 //!
 //! // read secrets from that base path
 //! let secrets = client.read_secrets::<MySecrets>().await.unwrap();
@@ -33,10 +33,20 @@
 mod auth;
 pub mod error;
 
+#[cfg(feature = "auth")]
+pub use auth::AuthMethod;
+
 use std::fs::File;
 use std::io::prelude::*;
+use std::sync::Arc;
+
+#[cfg(feature = "read")]
+use std::collections::HashMap;
 
 use serde::Deserialize;
+#[cfg(feature = "read")]
+use serde_json::Value;
+use tokio::sync::RwLock;
 use vaultrs::api::kv2::responses::ReadSecretMetadataResponse;
 use vaultrs::client::{VaultClient, VaultClientSettingsBuilder};
 use vaultrs::error::ClientError;
@@ -54,20 +64,29 @@ use vaultrs::api::kv2::responses::SecretVersionMetadata;
 use crate::error::Result;
 
 #[cfg(feature = "auth")]
-use crate::auth::login;
+use crate::auth::{login, RenewState};
 
 #[cfg(feature = "token")]
 const VAULT_TOKEN_PATH: &str = "/vault/secrets/token";
 
 /// A client to read secrets from Hashicorp Vault.
 ///
-/// The client is initialized with a VaultClient, the mount and a base path.
+/// The client is initialized with the address of the Vault instance, the mount and a base path.
+/// The underlying `VaultClient` is held behind a shared lock so it can be swapped out in place by
+/// the auto-renewal task started by [`SecretClient::with_auto_renew`], without every read/write
+/// call paying the cost of building a fresh HTTP client.
 ///
 /// <mount>/data/<base_path> where base_path reflects the lowest level of where secrets are located.
 pub struct SecretClient {
-    client: VaultClient,
+    #[cfg(feature = "auth")]
+    address: String,
     mount: String,
     base_path: String,
+    client: Arc<RwLock<VaultClient>>,
+    #[cfg(feature = "auth")]
+    renew_state: Option<RenewState>,
+    #[cfg(feature = "auth")]
+    renew_handle: Option<tokio::task::JoinHandle<()>>,
 }
 
 /// Options for confguring a write, the version will be used as cas value.
@@ -91,19 +110,21 @@ impl SecretClient {
     /// - mount is the mount point of the KV2 secrets engine.
     /// - base_path reflects the lowest level of where secrets are located
     /// - token is the Vault token to use. If no token is passed it tries to read the token from /vault/secrets/token.
+    /// - namespace is the Vault Enterprise namespace to address, if any.
     #[cfg(feature = "token")]
     pub fn new(
         address: &str,
         mount: String,
         base_path: String,
         token: Option<String>,
+        namespace: Option<String>,
     ) -> Result<SecretClient> {
         let token = match token {
             Some(token) => token,
-            None => read_token_from(VAULT_TOKEN_PATH)?,
+            None => read_token_from(VAULT_TOKEN_PATH, true)?,
         };
 
-        Self::create_internal(address, mount, base_path, &token)
+        Self::create_internal(address, mount, base_path, &token, namespace)
     }
 
     /// Convenience method to create a new SecretClient with a login to vault.
@@ -113,6 +134,7 @@ impl SecretClient {
     /// - role is the vault role to use for the login
     /// - mount is the mount point of the KV2 secrets engine
     /// - base_path reflects the lowest level of where secrets are located
+    /// - namespace is the Vault Enterprise namespace to address, if any.
     #[cfg(feature = "auth")]
     pub async fn create(
         address: &str,
@@ -120,10 +142,79 @@ impl SecretClient {
         role: &str,
         mount: String,
         base_path: String,
+        namespace: Option<String>,
     ) -> Result<SecretClient> {
-        let auth = login(address, auth_mount, role).await?;
+        Self::create_with(
+            address,
+            auth_mount,
+            mount,
+            base_path,
+            AuthMethod::Kubernetes {
+                role: role.to_string(),
+                token_path: None,
+            },
+            namespace,
+        )
+        .await
+    }
+
+    /// Create a new SecretClient by logging in to Vault with the given [`AuthMethod`].
+    ///
+    /// - address is the address of your Vault instance
+    /// - auth_mount is the mount path of the vault authentication backend
+    /// - mount is the mount point of the KV2 secrets engine
+    /// - base_path reflects the lowest level of where secrets are located
+    /// - method selects which Vault auth method to use and carries its credentials
+    /// - namespace is the Vault Enterprise namespace to address, if any.
+    #[cfg(feature = "auth")]
+    pub async fn create_with(
+        address: &str,
+        auth_mount: &str,
+        mount: String,
+        base_path: String,
+        method: AuthMethod,
+        namespace: Option<String>,
+    ) -> Result<SecretClient> {
+        let login_result = login(address, auth_mount, &method, namespace.as_deref()).await?;
+
+        let mut client = Self::create_internal(
+            address,
+            mount,
+            base_path,
+            &login_result.token,
+            namespace.clone(),
+        )?;
+        client.renew_state = Some(RenewState {
+            auth_mount: auth_mount.to_string(),
+            method,
+            lease_duration: login_result.lease_duration,
+            renewable: login_result.renewable,
+            namespace,
+        });
+
+        Ok(client)
+    }
+
+    /// Starts a background task that keeps this client's token fresh for as long as the
+    /// `SecretClient` is alive.
+    ///
+    /// Renewable tokens are renewed via `vaultrs::token::renew_self` at roughly half their TTL;
+    /// non-renewable tokens are refreshed by logging in again with the original `AuthMethod`.
+    /// A token whose lease has no duration, such as one given via `AuthMethod::Token`, is not
+    /// renewed or refreshed. Only has an effect on clients created via [`SecretClient::create`] or
+    /// [`SecretClient::create_with`]; the task is stopped when the `SecretClient` is dropped.
+    #[cfg(feature = "auth")]
+    pub fn with_auto_renew(mut self) -> Self {
+        let Some(state) = self.renew_state.take() else {
+            return self;
+        };
+
+        let address = self.address.clone();
+        let client = Arc::clone(&self.client);
 
-        Self::create_internal(address, mount, base_path, &auth.client_token)
+        self.renew_handle = Some(tokio::spawn(crate::auth::auto_renew(address, client, state)));
+
+        self
     }
 
     fn create_internal(
@@ -131,15 +222,21 @@ impl SecretClient {
         mount: String,
         base_path: String,
         token: &str,
+        namespace: Option<String>,
     ) -> Result<SecretClient> {
-        let client = VaultClient::new(
-            VaultClientSettingsBuilder::default()
-                .address(address)
-                .token(token)
-                .build()?,
-        )?;
-
-        Ok(SecretClient { client, mount, base_path })
+        let client = build_vault_client(address, token, namespace.as_deref())?;
+
+        Ok(SecretClient {
+            #[cfg(feature = "auth")]
+            address: address.to_string(),
+            mount,
+            base_path,
+            client: Arc::new(RwLock::new(client)),
+            #[cfg(feature = "auth")]
+            renew_state: None,
+            #[cfg(feature = "auth")]
+            renew_handle: None,
+        })
     }
 
     /// Read secrets from the base path.
@@ -171,8 +268,10 @@ impl SecretClient {
         A: for<'de> Deserialize<'de>,
     {
         let path = path.unwrap_or(&self.base_path);
-        let metadata: vaultrs::api::kv2::responses::ReadSecretMetadataResponse =
-            kv2::read_metadata(&self.client, &self.mount, path).await?;
+        let metadata: vaultrs::api::kv2::responses::ReadSecretMetadataResponse = {
+            let client = self.client.read().await;
+            kv2::read_metadata(&*client, &self.mount, path).await?
+        };
 
         let data = self
             .read_secrets_internal(path, Some(metadata.current_version))
@@ -186,9 +285,10 @@ impl SecretClient {
     where
         A: for<'de> Deserialize<'de>,
     {
+        let client = self.client.read().await;
         let secrets = match version {
-            Some(version) => kv2::read_version::<A>(&self.client, &self.mount, path, version).await,
-            None => kv2::read::<A>(&self.client, &self.mount, path).await,
+            Some(version) => kv2::read_version::<A>(&*client, &self.mount, path, version).await,
+            None => kv2::read::<A>(&*client, &self.mount, path).await,
         };
 
         if let Err(ClientError::APIError { code: 404, .. }) = secrets {
@@ -201,6 +301,46 @@ impl SecretClient {
         Ok(secrets?)
     }
 
+    /// Lists the child keys stored one level below the given path, or the base path if none is
+    /// given.
+    #[cfg(feature = "read")]
+    pub async fn list_secrets(&self, path: Option<&str>) -> Result<Vec<String>> {
+        let path = path.unwrap_or(&self.base_path);
+        let client = self.client.read().await;
+        let keys = kv2::list(&*client, &self.mount, path).await;
+
+        if let Err(ClientError::APIError { code: 404, .. }) = keys {
+            return Err(VaultierError::PathNotFound(format!(
+                "{mount}/metadata/{path}",
+                mount = self.mount
+            )));
+        }
+
+        Ok(keys?)
+    }
+
+    /// Reads a secret and deserializes only the requested subset of its fields.
+    ///
+    /// Useful when a single Vault path holds many unrelated credentials and the caller only
+    /// needs a couple of them. Like [`SecretClient::list_secrets`] and
+    /// [`SecretClient::read_secrets_with_metadata`], `path` is relative to the mount and defaults
+    /// to the base path when `None`.
+    #[cfg(feature = "read")]
+    pub async fn read_fields<A>(&self, path: Option<&str>, keys: &[&str]) -> Result<A>
+    where
+        A: for<'de> Deserialize<'de>,
+    {
+        let path = path.unwrap_or(&self.base_path);
+        let secret: HashMap<String, Value> = self.read_secrets_internal(path, None).await?;
+
+        let fields: HashMap<&str, Value> = keys
+            .iter()
+            .filter_map(|key| secret.get(*key).map(|value| (*key, value.clone())))
+            .collect();
+
+        Ok(serde_json::from_value(serde_json::to_value(fields)?)?)
+    }
+
     /// Set secrets in the base path.
     #[cfg(feature = "write")]
     pub async fn set_secrets<A>(&self, data: &A) -> Result<SecretVersionMetadata>
@@ -229,7 +369,8 @@ impl SecretClient {
     where
         A: Serialize,
     {
-        let auth_info = kv2::set(&self.client, &self.mount, path, data).await?;
+        let client = self.client.read().await;
+        let auth_info = kv2::set(&*client, &self.mount, path, data).await?;
         Ok(auth_info)
     }
 
@@ -242,11 +383,12 @@ impl SecretClient {
         A: Serialize,
     {
         let path = options.path.unwrap_or_else(|| &self.base_path);
+        let client = self.client.read().await;
 
         let auth_info = match options.version {
             Some(cas) => {
                 kv2::set_with_options(
-                    &self.client,
+                    &*client,
                     &self.mount,
                     path,
                     &options.data,
@@ -254,16 +396,134 @@ impl SecretClient {
                 )
                 .await?
             }
-            None => kv2::set(&self.client, &self.mount, path, &options.data).await?,
+            None => kv2::set(&*client, &self.mount, path, &options.data).await?,
         };
 
         Ok(auth_info)
     }
+
+    /// Soft-deletes the current (latest) version of a secret.
+    ///
+    /// The data is not destroyed and can be restored with [`SecretClient::undelete_versions`].
+    #[cfg(feature = "write")]
+    pub async fn delete_latest(&self, path: Option<&str>) -> Result<()> {
+        let path = path.unwrap_or(&self.base_path);
+        let client = self.client.read().await;
+        let result = kv2::delete_latest(&*client, &self.mount, path).await;
+
+        self.map_version_result(result, "data", path)
+    }
+
+    /// Soft-deletes the given versions of a secret.
+    ///
+    /// Deleted versions are not destroyed and can be restored with
+    /// [`SecretClient::undelete_versions`].
+    #[cfg(feature = "write")]
+    pub async fn delete_versions(&self, path: Option<&str>, versions: &[u64]) -> Result<()> {
+        let path = path.unwrap_or(&self.base_path);
+        let client = self.client.read().await;
+        let result = kv2::delete_versions(&*client, &self.mount, path, versions.to_vec()).await;
+
+        self.map_version_result(result, "delete", path)
+    }
+
+    /// Restores previously deleted versions of a secret.
+    #[cfg(feature = "write")]
+    pub async fn undelete_versions(&self, path: Option<&str>, versions: &[u64]) -> Result<()> {
+        let path = path.unwrap_or(&self.base_path);
+        let client = self.client.read().await;
+        let result = kv2::undelete_versions(&*client, &self.mount, path, versions.to_vec()).await;
+
+        self.map_version_result(result, "undelete", path)
+    }
+
+    /// Permanently removes the given versions of a secret.
+    ///
+    /// Unlike [`SecretClient::delete_versions`], destroyed versions cannot be restored.
+    #[cfg(feature = "write")]
+    pub async fn destroy_versions(&self, path: Option<&str>, versions: &[u64]) -> Result<()> {
+        let path = path.unwrap_or(&self.base_path);
+        let client = self.client.read().await;
+        let result = kv2::destroy_versions(&*client, &self.mount, path, versions.to_vec()).await;
+
+        self.map_version_result(result, "destroy", path)
+    }
+
+    #[cfg(feature = "write")]
+    fn map_version_result(
+        &self,
+        result: std::result::Result<(), ClientError>,
+        operation: &str,
+        path: &str,
+    ) -> Result<()> {
+        if let Err(ClientError::APIError { code: 404, .. }) = result {
+            return Err(VaultierError::PathNotFound(format!(
+                "{mount}/{operation}/{path}",
+                mount = self.mount
+            )));
+        }
+
+        Ok(result?)
+    }
+}
+
+#[cfg(feature = "auth")]
+impl Drop for SecretClient {
+    fn drop(&mut self) {
+        if let Some(handle) = self.renew_handle.take() {
+            handle.abort();
+        }
+    }
 }
 
-fn read_token_from(path: &str) -> Result<String> {
+/// Builds a `VaultClient` for the given address/token/namespace.
+///
+/// Shared by `SecretClient::create_internal` and the auto-renewal task, which rebuilds the
+/// client only when the token actually changes rather than on every read/write call.
+pub(crate) fn build_vault_client(
+    address: &str,
+    token: &str,
+    namespace: Option<&str>,
+) -> Result<VaultClient> {
+    let mut settings = VaultClientSettingsBuilder::default();
+    settings.address(address).token(token);
+    if let Some(namespace) = namespace {
+        settings.namespace(namespace.to_string());
+    }
+    Ok(VaultClient::new(settings.build()?)?)
+}
+
+/// Reads a token from the given path.
+///
+/// `enforce_permissions` gates the Unix group/world-readable check: it is enabled for the Vault
+/// token file (which we expect callers to write with owner-only permissions), but left disabled
+/// for the Kubernetes service-account token, since projected volumes mount that file `0644` by
+/// default and that default is outside the caller's control.
+fn read_token_from(path: &str, enforce_permissions: bool) -> Result<String> {
+    #[cfg(unix)]
+    if enforce_permissions {
+        check_permissions(path)?;
+    }
+    #[cfg(not(unix))]
+    let _ = enforce_permissions;
+
     let mut file = File::open(path)?;
     let mut token = String::new();
     file.read_to_string(&mut token)?;
     Ok(token)
 }
+
+/// Rejects token files that are readable by the file's group or by anyone else, the same way
+/// the Kanidm client guards its token cache. A token written with insecure permissions (e.g.
+/// `0644`) could be read by any other process on the host.
+#[cfg(unix)]
+fn check_permissions(path: &str) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o044 != 0 {
+        return Err(VaultierError::InsecureTokenFilePermissions(path.to_string()));
+    }
+
+    Ok(())
+}